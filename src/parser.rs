@@ -1,181 +1,373 @@
 use regex::Regex;
+use std::fmt;
 use std::iter::Peekable;
-use std::vec::IntoIter; 
+use std::vec::IntoIter;
 
 //Les types de token
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
-    Atom(String),      
-    Not,               
-    And,           
-    Or,            
-    If,            
-    Iff,        
-    Then,      
-    OpenParen,    
+    Atom(String),
+    Not,
+    And,
+    Or,
+    Xor,
+    If,
+    Iff,
+    Then,
+    OpenParen,
     CloseParen,
 }
 
+impl Token {
+    // Puissance de liaison a gauche (left binding power) de l'operateur.
+    // Plus la valeur est haute, plus l'operateur lie fort : and > or/xor > if > iff.
+    // Les tokens qui ne sont pas des operateurs infixes n'en lient aucun (0).
+    fn lbp(&self) -> u8 {
+        match self {
+            Token::Iff => 10,
+            Token::If => 20,
+            Token::Or | Token::Xor => 30,
+            Token::And => 40,
+            _ => 0,
+        }
+    }
+}
+
+// Puissance de liaison du "not" prefixe.
+const NOT_BP: u8 = 50;
+
+// Decalage en octets `(debut, fin)` d'un token ou d'une expression dans
+// l'entree source.
+pub type Span = (usize, usize);
+
+// Associe un noeud a la portion de l'entree source dont il provient, pour
+// que l'appelant puisse souligner la formule entiere dans ses diagnostics.
+#[derive(Debug, Clone)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    fn new(node: T, span: Span) -> Self {
+        Spanned { node, span }
+    }
+}
+
+// Erreur de lexing/parsing, avec la portion de l'entree ou elle a ete
+// detectee. Permet a l'appelant de produire un diagnostic qui souligne le
+// texte fautif au lieu de faire paniquer le programme.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    UnexpectedToken { found: Token, span: Span },
+    MissingClosingParen { span: Span },
+    UnexpectedThen { span: Span },
+    AdjacentAtoms { span: Span },
+    UnexpectedEof,
+}
+
 //Les types de noeuds de l'AST (abstract syntax tree)
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AstNode {
     Atom(String),
+    Const(bool), // Introduit par `simplify::simplify` lors du constant-folding.
     Not(Box<AstNode>),
     And(Box<AstNode>, Box<AstNode>),
     Or(Box<AstNode>, Box<AstNode>),
+    Xor(Box<AstNode>, Box<AstNode>),
     If(Box<AstNode>, Box<AstNode>),
     Iff(Box<AstNode>, Box<AstNode>),
 }
 
+// Reconstruit une formule lisible depuis l'AST, pour l'affichage dans le REPL
+// (commandes `list` et `simplify`).
+impl fmt::Display for AstNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AstNode::Atom(name) => write!(f, "{}", name),
+            AstNode::Const(true) => write!(f, "true"),
+            AstNode::Const(false) => write!(f, "false"),
+            AstNode::Not(expr) => write!(f, "not {}", expr),
+            AstNode::And(left, right) => write!(f, "({} and {})", left, right),
+            AstNode::Or(left, right) => write!(f, "({} or {})", left, right),
+            AstNode::Xor(left, right) => write!(f, "({} xor {})", left, right),
+            AstNode::If(left, right) => write!(f, "({} if {})", left, right),
+            AstNode::Iff(left, right) => write!(f, "({} iff {})", left, right),
+        }
+    }
+}
+
 pub struct Parser {
-    tokens: Peekable<IntoIter<Token>>, // Stocke les tokens en cours de traitement.
+    tokens: Peekable<IntoIter<(Token, Span)>>, // Stocke les tokens (et leur portion source) en cours de traitement.
 }
 
 impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Self {
+    pub fn new(tokens: Vec<(Token, Span)>) -> Self {
         Parser {
             tokens: tokens.into_iter().peekable(), // Initialise un iterateur
         }
     }
 
-    // Analyse les expressions primaires en fonction du token.
-    fn parse_primary(&mut self) -> Option<AstNode> {
+    // Analyse une expression primaire : atome, "not <expr>", "( <expr> )"
+    // ou la forme conditionnelle prefixe "if <cond> then <consequence>".
+    fn parse_primary(&mut self) -> Result<Spanned<AstNode>, ParseError> {
         match self.tokens.next() {
-            Some(Token::If) => self.parse_conditional(), 
-            Some(Token::Iff) => self.parse_conditional(),
-            Some(Token::Atom(atom)) => Some(AstNode::Atom(atom)),
-            Some(Token::Not) => self.parse_not(),
-            Some(Token::OpenParen) => self.parse_parenthesized_expr(),
-            Some(Token::Then) => {
-                panic!("Unexpected 'then' keyword without preceding 'if'");
-            }
-            _ => None, 
+            Some((Token::Atom(atom), span)) => Ok(Spanned::new(AstNode::Atom(atom), span)),
+            Some((Token::Not, span)) => self.parse_not(span),
+            Some((Token::OpenParen, span)) => self.parse_parenthesized_expr(span),
+            Some((Token::If, span)) => self.parse_conditional(span),
+            Some((Token::Then, span)) => Err(ParseError::UnexpectedThen { span }),
+            Some((found, span)) => Err(ParseError::UnexpectedToken { found, span }),
+            None => Err(ParseError::UnexpectedEof),
         }
     }
 
-    // Analyser l'opérateur NOT et verifie qu'il est suivi d'un atome dans l'AST.
-    fn parse_not(&mut self) -> Option<AstNode> {
-        match self.parse_primary() {
-            Some(expr) => Some(AstNode::Not(Box::new(expr))),
-            None => panic!("Expected expression after NOT"),
-        }
+    // Analyser l'operateur NOT (prefixe) et ce qui le suit. `not_span` est la
+    // portion source du mot-cle "not", utilisee pour etendre la portion du
+    // noeud resultant jusqu'a la fin de son operande.
+    fn parse_not(&mut self, not_span: Span) -> Result<Spanned<AstNode>, ParseError> {
+        let expr = self.parse_expression(NOT_BP)?;
+        let span = (not_span.0, expr.span.1);
+        Ok(Spanned::new(AstNode::Not(Box::new(expr.node)), span))
     }
 
-    // Analyser les opérations binaires.
-    fn parse_binary_op( &mut self, parse_left: fn(&mut Parser) -> Option<AstNode>, ops: &[Token],) -> Option<AstNode> {
-        let mut left = parse_left(self)?; // Recupere le noeud voisin de gauche
-        while let Some(op) = self.tokens.peek().cloned() {
-            if ops.contains(&op) {
-                let tokens = self.tokens.by_ref();
-                tokens.next();
-                let right = parse_left(self)?; // Recupere le noeud voisin de droite
-                left = match op { // Construit le noeud de l'AST correspondant à l'opération binaire.
-                    Token::And => AstNode::And(Box::new(left), Box::new(right)),
-                    Token::Or => AstNode::Or(Box::new(left), Box::new(right)),
-                    Token::If => AstNode::If(Box::new(left), Box::new(right)),
-                    Token::Iff => AstNode::Iff(Box::new(left), Box::new(right)),
-                    _ => unreachable!(), // Erreur si un token non géré est rencontré.
-                };
-            } else {
-                break; // Arrête la boucle si le token n'est pas un opérateur géré.
+    // Coeur du parseur de Pratt (top-down operator precedence) : analyse une
+    // primaire puis consomme les operateurs infixes dont le lbp depasse
+    // `min_bp`, en rappelant recursivement pour l'operande de droite.
+    // `and`/`or`/`iff` sont associatifs a gauche (on rappelle avec `lbp`),
+    // `if` est associatif a droite (on rappelle avec `lbp - 1`) pour que
+    // `a if b if c` se lise `a if (b if c)`.
+    fn parse_expression(&mut self, min_bp: u8) -> Result<Spanned<AstNode>, ParseError> {
+        let mut left = self.parse_primary()?;
+
+        while let Some((op, _)) = self.tokens.peek().cloned() {
+            let op_lbp = op.lbp();
+            if op_lbp <= min_bp {
+                break;
             }
+            self.tokens.next();
+
+            let next_min_bp = if op == Token::If { op_lbp - 1 } else { op_lbp };
+            let right = self.parse_expression(next_min_bp)?;
+            let span = (left.span.0, right.span.1);
+
+            let node = match op {
+                Token::And => AstNode::And(Box::new(left.node), Box::new(right.node)),
+                Token::Or => AstNode::Or(Box::new(left.node), Box::new(right.node)),
+                Token::Xor => AstNode::Xor(Box::new(left.node), Box::new(right.node)),
+                Token::If => AstNode::If(Box::new(left.node), Box::new(right.node)),
+                Token::Iff => AstNode::Iff(Box::new(left.node), Box::new(right.node)),
+                _ => unreachable!(), // lbp() == 0 pour tout le reste, donc jamais atteint ici.
+            };
+            left = Spanned::new(node, span);
         }
-        Some(left) // Retourne le nœud d'AST résultant.
-    }
 
-    // Analyser une expression.
-    fn parse_expr(&mut self) -> Option<AstNode> {
-        self.parse_binary_op(Parser::parse_primary, &[Token::And, Token::Or])
+        Ok(left)
     }
 
-    // Analyser une expression conditionnelle IF.
-    fn parse_conditional(&mut self) -> Option<AstNode> {
-        let condition = self.parse_expr()?;
-        if let Some(Token::Then) = self.tokens.next() { // Vérifie si le token suivant est "then".
-            let consequence = self.parse_expr()?;
-            Some(AstNode::If(Box::new(condition), Box::new(consequence))) // Retourne un nœud de l'AST pour l'expression conditionnelle.
-        } else {
-            panic!("Expected 'then' keyword"); // Erreur si "then" est manquant après une expression conditionnelle.
+    // Analyser une expression conditionnelle "if <cond> then <consequence>".
+    // `if_span` est la portion source du mot-cle "if" qui a mene ici.
+    fn parse_conditional(&mut self, if_span: Span) -> Result<Spanned<AstNode>, ParseError> {
+        let condition = self.parse_expression(0)?;
+        match self.tokens.next() {
+            Some((Token::Then, _)) => {
+                let consequence = self.parse_expression(0)?;
+                let span = (if_span.0, consequence.span.1);
+                Ok(Spanned::new(AstNode::If(Box::new(condition.node), Box::new(consequence.node)), span))
+            }
+            Some((found, span)) => Err(ParseError::UnexpectedToken { found, span }),
+            None => Err(ParseError::UnexpectedEof),
         }
     }
 
-    // Analyser une expression entre parenthèses.
-    fn parse_parenthesized_expr(&mut self) -> Option<AstNode> {
-        self.tokens.next(); // Avance au token suivant, qui doit être une expression.
-        let expr = self.parse_expr()?;
-        if let Some(Token::CloseParen) = self.tokens.peek() { // Vérifie si le token suivant est une parenthèse fermante.
-            self.tokens.next();
-        } else {
-            panic!("Missing closing parenthesis"); // Erreur si une parenthèse fermante est manquante.
+    // Analyser une expression entre parenthèses. `open_span` est la portion
+    // source de la parenthèse ouvrante.
+    fn parse_parenthesized_expr(&mut self, open_span: Span) -> Result<Spanned<AstNode>, ParseError> {
+        let expr = self.parse_expression(0)?;
+        match self.tokens.peek().cloned() {
+            Some((Token::CloseParen, close_span)) => {
+                self.tokens.next();
+                Ok(Spanned::new(expr.node, (open_span.0, close_span.1)))
+            }
+            Some((_, span)) => Err(ParseError::MissingClosingParen { span }),
+            None => Err(ParseError::UnexpectedEof),
         }
-        Some(expr) // Retourne l'expression analysée.
     }
 
-    // Fonction principale pour analyser l'entrée et construire l'AST.
-    pub fn parse(&mut self) -> Option<AstNode> {
-        self.parse_expr()
+    // Fonction principale pour analyser l'entrée et construire l'AST. Verifie
+    // qu'il ne reste aucun token apres l'expression de plus haut niveau, pour
+    // qu'une entree comme "a and b)" produise une erreur au lieu d'ignorer
+    // silencieusement le ")" en trop.
+    pub fn parse(&mut self) -> Result<Spanned<AstNode>, ParseError> {
+        let expr = self.parse_expression(0)?;
+        match self.tokens.next() {
+            Some((found, span)) => Err(ParseError::UnexpectedToken { found, span }),
+            None => Ok(expr),
+        }
     }
 }
 
-// Fonction pour le lexing de l'entrée et la production de tokens.
-pub fn lex(input: &str) -> Vec<Token> {
-    let re = Regex::new(r"[a-zA-Z]+|not|and|or|if|iff|then|\(|\)").unwrap();
+// Fonction pour le lexing de l'entrée et la production de tokens, chacun
+// accompagné de la portion `(debut, fin)` en octets qu'il occupe dans `input`.
+pub fn lex(input: &str) -> Result<Vec<(Token, Span)>, ParseError> {
+    let re = Regex::new(r"[a-zA-Z]+|not|and|or|xor|if|iff|then|\(|\)").unwrap();
     let mut tokens = Vec::new(); // Initialise un vecteur pour stocker les tokens.
     let mut last_token_was_atom = false; // Vrai si le dernier token était un atome.
-    let mut in_conditional = false; // Vrai si on est à l'intérieur d'une expression conditionnelle.
+    // Nombre de "if" deja vus (prefixes ou infixes : le lexer ne les
+    // distingue pas) qui n'ont pas encore ete apparies a un "then". Un
+    // compteur (plutot qu'un booleen jamais remis a false) pour qu'un "then"
+    // en trop soit rejete meme apres un "if...then" deja complet.
+    let mut open_conditionals: u32 = 0;
 
     // Parcourt l'entrée en utilisant le pattern regex.
-    for mat in re.find_iter(input) { 
+    for mat in re.find_iter(input) {
+        let span = (mat.start(), mat.end());
         // Ajoute un token selon l'entrée recu
         match mat.as_str() {
             "not" => {
-                tokens.push(Token::Not);
+                tokens.push((Token::Not, span));
                 last_token_was_atom = false;
             }
             "and" => {
-                tokens.push(Token::And);
+                tokens.push((Token::And, span));
                 last_token_was_atom = false;
             }
             "or" => {
-                tokens.push(Token::Or);
+                tokens.push((Token::Or, span));
+                last_token_was_atom = false;
+            }
+            "xor" => {
+                tokens.push((Token::Xor, span));
                 last_token_was_atom = false;
             }
             "if" => {
-                tokens.push(Token::If);
-                in_conditional = true;
+                tokens.push((Token::If, span));
+                open_conditionals += 1;
                 last_token_was_atom = false;
             }
             "iff" => {
-                tokens.push(Token::Iff);
-                in_conditional = true;
+                // "iff" est un operateur infixe ("a iff b") : contrairement a
+                // "if", il n'est jamais suivi d'un "then" et ne doit donc pas
+                // ouvrir de conditionnelle.
+                tokens.push((Token::Iff, span));
                 last_token_was_atom = false;
             }
             "then" => {
-                // Verifie si on se trouve dans une boucle conditionnel
-                if !in_conditional {
-                    panic!("Unexpected 'then' keyword without preceding 'if'");
+                // Verifie qu'il reste un "if" en attente de son "then".
+                if open_conditionals == 0 {
+                    return Err(ParseError::UnexpectedThen { span });
                 }
-                tokens.push(Token::Then);
+                open_conditionals -= 1;
+                tokens.push((Token::Then, span));
                 last_token_was_atom = false;
             }
             "(" => {
-                tokens.push(Token::OpenParen);
+                tokens.push((Token::OpenParen, span));
                 last_token_was_atom = false;
             }
             ")" => {
-                tokens.push(Token::CloseParen);
+                tokens.push((Token::CloseParen, span));
                 last_token_was_atom = false;
             }
             atom => {
-                tokens.push(Token::Atom(atom.to_string()));
                 // Verifie que le dernier token n'est pas un atom
                 if last_token_was_atom {
-                    panic!("Expected operator between atoms");
+                    return Err(ParseError::AdjacentAtoms { span });
                 }
+                tokens.push((Token::Atom(atom.to_string()), span));
                 last_token_was_atom = true;
             }
         }
     }
 
-    tokens // Retourne le vecteur de tokens produit.
+    Ok(tokens) // Retourne le vecteur de tokens produit.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(name: &str) -> AstNode {
+        AstNode::Atom(name.to_string())
+    }
+
+    fn parse_ok(input: &str) -> AstNode {
+        let tokens = lex(input).expect("lex should succeed");
+        Parser::new(tokens).parse().expect("parse should succeed").node
+    }
+
+    fn parse_err(input: &str) -> ParseError {
+        match lex(input) {
+            Ok(tokens) => Parser::new(tokens).parse().expect_err("parse should fail"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let ast = parse_ok("a or b and c");
+        assert_eq!(
+            ast,
+            AstNode::Or(Box::new(atom("a")), Box::new(AstNode::And(Box::new(atom("b")), Box::new(atom("c")))))
+        );
+    }
+
+    #[test]
+    fn if_is_right_associative() {
+        // "a if b if c" doit se lire "a if (b if c)".
+        let ast = parse_ok("a if b if c");
+        assert_eq!(
+            ast,
+            AstNode::If(Box::new(atom("a")), Box::new(AstNode::If(Box::new(atom("b")), Box::new(atom("c")))))
+        );
+    }
+
+    #[test]
+    fn iff_is_left_associative() {
+        // "a iff b iff c" doit se lire "(a iff b) iff c".
+        let ast = parse_ok("a iff b iff c");
+        assert_eq!(
+            ast,
+            AstNode::Iff(Box::new(AstNode::Iff(Box::new(atom("a")), Box::new(atom("b")))), Box::new(atom("c")))
+        );
+    }
+
+    #[test]
+    fn prefix_if_then_and_infix_if_coexist() {
+        let prefix = parse_ok("if a then b");
+        let infix = parse_ok("a if b");
+        let expected = AstNode::If(Box::new(atom("a")), Box::new(atom("b")));
+        assert_eq!(prefix, expected);
+        assert_eq!(infix, expected);
+
+        let nested = parse_ok("if a then b and c");
+        assert_eq!(
+            nested,
+            AstNode::If(Box::new(atom("a")), Box::new(AstNode::And(Box::new(atom("b")), Box::new(atom("c")))))
+        );
+    }
+
+    #[test]
+    fn trailing_tokens_after_a_valid_expression_are_rejected() {
+        let err = parse_err("a and b)");
+        assert_eq!(err, ParseError::UnexpectedToken { found: Token::CloseParen, span: (7, 8) });
+    }
+
+    #[test]
+    fn then_without_a_matching_if_is_rejected() {
+        let err = parse_err("if a then b then c");
+        assert_eq!(err, ParseError::UnexpectedThen { span: (12, 16) });
+    }
+
+    #[test]
+    fn unclosed_parenthesis_is_rejected() {
+        let err = parse_err("(a and b");
+        assert_eq!(err, ParseError::UnexpectedEof);
+    }
+
+    #[test]
+    fn adjacent_atoms_are_rejected() {
+        let err = parse_err("a b");
+        assert_eq!(err, ParseError::AdjacentAtoms { span: (2, 3) });
+    }
 }
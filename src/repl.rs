@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use crate::parser::{self, AstNode, ParseError, Span};
+use crate::sat;
+use crate::simplify;
+
+// Boucle interactive : garde une base de connaissances (KB) vivante entre les
+// commandes plutot que de lire une KB et une formule une seule fois puis de
+// quitter. Chaque ligne est une commande parmi :
+//   assert <formule>    ajoute <formule> a la KB
+//   retract <n>         retire la formule #n de la KB
+//   list                affiche la KB courante
+//   entails <formule>   verifie si KB |= <formule>
+//   sat <formule>       verifie si <formule> est satisfaisable
+//   valid <formule>     verifie si <formule> est une tautologie
+//   model <formule>     affiche une affectation qui satisfait <formule>
+//   simplify <formule>  affiche la formule brute et sa forme canonicalisee
+//   reset               vide la KB
+//   quit                quitte le REPL
+pub fn run() {
+    let mut kb: Vec<AstNode> = Vec::new();
+
+    println!("Propositional logic REPL.");
+    println!("Commands: assert, retract, list, entails, sat, valid, model, simplify, reset, quit");
+
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line).unwrap() == 0 {
+            break; // Entrée fermée (EOF).
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (command, argument) = match line.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line, ""),
+        };
+
+        match command {
+            "assert" => match parse_formula(argument) {
+                Ok(ast) => {
+                    kb.push(ast);
+                    println!("Asserted as #{}.", kb.len() - 1);
+                }
+                Err(err) => report_parse_error(argument, &err),
+            },
+            "retract" => match argument.parse::<usize>() {
+                Ok(index) if index < kb.len() => {
+                    kb.remove(index);
+                    println!("Retracted #{}.", index);
+                }
+                _ => println!("No formula #{} in the knowledge base.", argument),
+            },
+            "list" => {
+                if kb.is_empty() {
+                    println!("Knowledge base is empty.");
+                } else {
+                    for (index, ast) in kb.iter().enumerate() {
+                        println!("{}: {}", index, ast);
+                    }
+                }
+            }
+            "entails" => match parse_formula(argument) {
+                Ok(alpha) => {
+                    if sat::entails(&kb, &alpha) {
+                        println!("KB |= α");
+                    } else {
+                        println!("KB |≠ α");
+                    }
+                }
+                Err(err) => report_parse_error(argument, &err),
+            },
+            "sat" => match parse_formula(argument) {
+                Ok(ast) => println!("{}", if sat::is_satisfiable(&ast) { "satisfiable" } else { "unsatisfiable" }),
+                Err(err) => report_parse_error(argument, &err),
+            },
+            "valid" => match parse_formula(argument) {
+                Ok(ast) => println!("{}", if sat::is_valid(&ast) { "valid" } else { "not valid" }),
+                Err(err) => report_parse_error(argument, &err),
+            },
+            "model" => match parse_formula(argument) {
+                Ok(ast) => match sat::find_model(&ast) {
+                    Some(model) => println!("{}", format_model(&model)),
+                    None => println!("No satisfying assignment exists."),
+                },
+                Err(err) => report_parse_error(argument, &err),
+            },
+            "simplify" => match parse_formula(argument) {
+                Ok(ast) => println!("raw: {}\nsimplified: {}", ast, simplify::simplify(&ast)),
+                Err(err) => report_parse_error(argument, &err),
+            },
+            "reset" => {
+                kb.clear();
+                println!("Knowledge base cleared.");
+            }
+            "quit" => break,
+            _ => println!("Unknown command: {}", command),
+        }
+    }
+}
+
+// Lex puis parse une formule, sans paniquer en cas d'entrée invalide.
+fn parse_formula(formula: &str) -> Result<AstNode, ParseError> {
+    let tokens = parser::lex(formula)?;
+    let mut parser = parser::Parser::new(tokens);
+    Ok(parser.parse()?.node)
+}
+
+// Affiche un diagnostic lisible pour une erreur de syntaxe, avec la portion
+// fautive soulignée dans la formule d'origine.
+fn report_parse_error(formula: &str, err: &ParseError) {
+    let message = match err {
+        ParseError::UnexpectedToken { found, span } => {
+            print_underline(formula, *span);
+            format!("unexpected token {:?}", found)
+        }
+        ParseError::MissingClosingParen { span } => {
+            print_underline(formula, *span);
+            "missing closing parenthesis".to_string()
+        }
+        ParseError::UnexpectedThen { span } => {
+            print_underline(formula, *span);
+            "'then' keyword without a preceding 'if'".to_string()
+        }
+        ParseError::AdjacentAtoms { span } => {
+            print_underline(formula, *span);
+            "expected an operator between atoms".to_string()
+        }
+        ParseError::UnexpectedEof => {
+            print_underline(formula, (formula.len(), formula.len()));
+            "unexpected end of input".to_string()
+        }
+    };
+
+    println!("Syntax error: {}", message);
+}
+
+// Affiche la formule puis souligne la portion `(debut, fin)` donnée avec des `^`.
+fn print_underline(formula: &str, span: Span) {
+    println!("{}", formula);
+    let (start, end) = span;
+    let width = end.saturating_sub(start).max(1);
+    println!("{}{}", " ".repeat(start), "^".repeat(width));
+}
+
+// Affiche une affectation triée par nom d'atome, pour un résultat déterministe.
+fn format_model(model: &HashMap<String, bool>) -> String {
+    let mut atoms: Vec<&String> = model.keys().collect();
+    atoms.sort();
+    atoms
+        .into_iter()
+        .map(|atom| format!("{}={}", atom, model[atom]))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
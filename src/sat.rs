@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+
+use crate::parser::AstNode;
+
+// Un litteral est un entier signe indexant une variable : `v` signifie que
+// la variable `v` doit etre vraie, `-v` qu'elle doit etre fausse.
+pub type Lit = i32;
+
+// Au-dela de ce nombre de clauses, la distribution naive de Or sur And est
+// abandonnee au profit d'un encodage de Tseitin (taille lineaire au lieu
+// d'exponentielle dans la taille de la formule).
+const MAX_NAIVE_CLAUSES: usize = 4096;
+
+// Verifie si `ast` est satisfaisable.
+pub fn is_satisfiable(ast: &AstNode) -> bool {
+    find_model(ast).is_some()
+}
+
+// `ast` est valide (une tautologie) ssi sa negation est insatisfaisable.
+pub fn is_valid(ast: &AstNode) -> bool {
+    !is_satisfiable(&AstNode::Not(Box::new(ast.clone())))
+}
+
+// KB |= alpha ssi (KB et non-alpha) est insatisfaisable.
+pub fn entails(kb: &[AstNode], alpha: &AstNode) -> bool {
+    let mut formula = AstNode::Not(Box::new(alpha.clone()));
+    for ast in kb {
+        formula = AstNode::And(Box::new(ast.clone()), Box::new(formula));
+    }
+    !is_satisfiable(&formula)
+}
+
+// Cherche une affectation qui satisfait `ast`, s'il en existe une. L'ensemble
+// des atomes renvoyes est celui de `ast` *avant* simplification : `to_cnf`
+// simplifie la formule en interne, ce qui peut eliminer des atomes (par
+// exemple `a` dans `a ou non a`) qui seraient alors absents de `atom_ids`
+// tout en etant bel et bien presents dans la formule de l'utilisateur.
+pub fn find_model(ast: &AstNode) -> Option<HashMap<String, bool>> {
+    let (clauses, atom_ids) = to_cnf(ast);
+    let assignment = solve(clauses)?;
+    let mut original_atoms = HashMap::new();
+    collect_atom_ids(ast, &mut original_atoms);
+    Some(decode_model(&original_atoms, &atom_ids, &assignment))
+}
+
+// Construit le modele final a partir de l'affectation trouvee par le solveur.
+// `original_atoms` porte les noms a renvoyer (ceux de la formule d'origine) ;
+// `atom_ids` fait le lien entre ces noms et les litteraux du solveur quand
+// l'atome a survecu a la simplification. Un atome absent d'`atom_ids` a ete
+// elimine par simplification : il n'est pas contraint, donc toute valeur lui
+// convient.
+fn decode_model(
+    original_atoms: &HashMap<String, i32>,
+    atom_ids: &HashMap<String, i32>,
+    assignment: &HashMap<i32, bool>,
+) -> HashMap<String, bool> {
+    original_atoms
+        .keys()
+        .map(|name| {
+            let value = atom_ids.get(name).and_then(|id| assignment.get(id)).copied().unwrap_or(false);
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+// Convertit une formule en CNF : (0) simplifie/canonicalise la formule pour
+// reduire sa taille, (1) elimine Xor/If/Iff, (2) pousse les Not vers les
+// atomes (forme normale negative), (3) distribue Or sur And pour obtenir
+// une conjonction de clauses, avec repli sur Tseitin si ca explose.
+fn to_cnf(ast: &AstNode) -> (Vec<Vec<Lit>>, HashMap<String, i32>) {
+    let simplified = crate::simplify::simplify(ast);
+    let nnf = to_nnf(&eliminate_connectives(&simplified), false);
+
+    let mut atom_ids = HashMap::new();
+    match distribute(&nnf, &mut atom_ids, MAX_NAIVE_CLAUSES) {
+        Some(clauses) => (clauses, atom_ids),
+        None => {
+            atom_ids.clear();
+            let clauses = tseitin_cnf(&nnf, &mut atom_ids);
+            (clauses, atom_ids)
+        }
+    }
+}
+
+// Elimine If(a, b) -> (non a ou b), Iff(a, b) -> (a -> b) et (b -> a), et
+// Xor(a, b) -> (a et non b) ou (non a et b).
+fn eliminate_connectives(ast: &AstNode) -> AstNode {
+    match ast {
+        AstNode::Atom(name) => AstNode::Atom(name.clone()),
+        AstNode::Const(value) => AstNode::Const(*value),
+        AstNode::Not(inner) => AstNode::Not(Box::new(eliminate_connectives(inner))),
+        AstNode::And(left, right) => AstNode::And(
+            Box::new(eliminate_connectives(left)),
+            Box::new(eliminate_connectives(right)),
+        ),
+        AstNode::Or(left, right) => AstNode::Or(
+            Box::new(eliminate_connectives(left)),
+            Box::new(eliminate_connectives(right)),
+        ),
+        AstNode::Xor(left, right) => {
+            let left = eliminate_connectives(left);
+            let right = eliminate_connectives(right);
+            let positive = AstNode::And(Box::new(left.clone()), Box::new(AstNode::Not(Box::new(right.clone()))));
+            let negative = AstNode::And(Box::new(AstNode::Not(Box::new(left))), Box::new(right));
+            AstNode::Or(Box::new(positive), Box::new(negative))
+        }
+        AstNode::If(left, right) => {
+            let left = eliminate_connectives(left);
+            let right = eliminate_connectives(right);
+            AstNode::Or(Box::new(AstNode::Not(Box::new(left))), Box::new(right))
+        }
+        AstNode::Iff(left, right) => {
+            let left = eliminate_connectives(left);
+            let right = eliminate_connectives(right);
+            let forward = AstNode::Or(Box::new(AstNode::Not(Box::new(left.clone()))), Box::new(right.clone()));
+            let backward = AstNode::Or(Box::new(AstNode::Not(Box::new(right))), Box::new(left));
+            AstNode::And(Box::new(forward), Box::new(backward))
+        }
+    }
+}
+
+// Pousse les Not vers les feuilles par les lois de De Morgan, en eliminant
+// au passage les doubles negations. `negate` indique si le sous-arbre
+// courant doit etre lu sous une negation.
+fn to_nnf(ast: &AstNode, negate: bool) -> AstNode {
+    match ast {
+        AstNode::Atom(name) => {
+            let atom = AstNode::Atom(name.clone());
+            if negate {
+                AstNode::Not(Box::new(atom))
+            } else {
+                atom
+            }
+        }
+        AstNode::Const(value) => AstNode::Const(if negate { !value } else { *value }),
+        AstNode::Not(inner) => to_nnf(inner, !negate),
+        AstNode::And(left, right) if negate => {
+            AstNode::Or(Box::new(to_nnf(left, true)), Box::new(to_nnf(right, true)))
+        }
+        AstNode::And(left, right) => AstNode::And(Box::new(to_nnf(left, false)), Box::new(to_nnf(right, false))),
+        AstNode::Or(left, right) if negate => {
+            AstNode::And(Box::new(to_nnf(left, true)), Box::new(to_nnf(right, true)))
+        }
+        AstNode::Or(left, right) => AstNode::Or(Box::new(to_nnf(left, false)), Box::new(to_nnf(right, false))),
+        AstNode::Xor(_, _) | AstNode::If(_, _) | AstNode::Iff(_, _) => {
+            unreachable!("eliminate_connectives already removed Xor/If/Iff")
+        }
+    }
+}
+
+fn atom_id(atom_ids: &mut HashMap<String, i32>, name: &str) -> Lit {
+    if let Some(&id) = atom_ids.get(name) {
+        return id;
+    }
+    let id = atom_ids.len() as i32 + 1;
+    atom_ids.insert(name.to_string(), id);
+    id
+}
+
+// Distribue Or sur And pour une formule en NNF. Renvoie `None` si le nombre
+// de clauses produites depasserait `cap`, pour laisser l'appelant retomber
+// sur l'encodage de Tseitin plutot que d'exploser en memoire.
+fn distribute(ast: &AstNode, atom_ids: &mut HashMap<String, i32>, cap: usize) -> Option<Vec<Vec<Lit>>> {
+    match ast {
+        AstNode::Atom(name) => Some(vec![vec![atom_id(atom_ids, name)]]),
+        AstNode::Const(true) => Some(Vec::new()), // Vrai : aucune clause a satisfaire.
+        AstNode::Const(false) => Some(vec![Vec::new()]), // Faux : une clause vide, toujours insatisfaite.
+        AstNode::Not(inner) => match inner.as_ref() {
+            AstNode::Atom(name) => Some(vec![vec![-atom_id(atom_ids, name)]]),
+            _ => unreachable!("NNF only negates atoms"),
+        },
+        AstNode::And(left, right) => {
+            let mut clauses = distribute(left, atom_ids, cap)?;
+            clauses.extend(distribute(right, atom_ids, cap)?);
+            (clauses.len() <= cap).then_some(clauses)
+        }
+        AstNode::Or(left, right) => {
+            let left_clauses = distribute(left, atom_ids, cap)?;
+            let right_clauses = distribute(right, atom_ids, cap)?;
+            if left_clauses.len().saturating_mul(right_clauses.len()) > cap {
+                return None;
+            }
+            let mut clauses = Vec::with_capacity(left_clauses.len() * right_clauses.len());
+            for left_clause in &left_clauses {
+                for right_clause in &right_clauses {
+                    let mut clause = left_clause.clone();
+                    clause.extend(right_clause.iter().copied());
+                    clauses.push(clause);
+                }
+            }
+            Some(clauses)
+        }
+        AstNode::Xor(_, _) | AstNode::If(_, _) | AstNode::Iff(_, _) => {
+            unreachable!("eliminate_connectives already removed Xor/If/Iff")
+        }
+    }
+}
+
+// Encodage de Tseitin : introduit une variable fraiche par sous-formule,
+// avec les clauses qui la definissent comme equivalente a ce sous-arbre,
+// puis force la variable de la racine a vrai. Taille lineaire dans la
+// formule, contrairement a la distribution naive.
+fn tseitin_cnf(ast: &AstNode, atom_ids: &mut HashMap<String, i32>) -> Vec<Vec<Lit>> {
+    collect_atom_ids(ast, atom_ids);
+    let mut next_aux = atom_ids.len() as i32 + 1;
+    let mut clauses = Vec::new();
+    let root = tseitin_var(ast, atom_ids, &mut next_aux, &mut clauses);
+    clauses.push(vec![root]);
+    clauses
+}
+
+fn collect_atom_ids(ast: &AstNode, atom_ids: &mut HashMap<String, i32>) {
+    match ast {
+        AstNode::Atom(name) => {
+            atom_id(atom_ids, name);
+        }
+        AstNode::Const(_) => {}
+        AstNode::Not(inner) => collect_atom_ids(inner, atom_ids),
+        AstNode::And(left, right)
+        | AstNode::Or(left, right)
+        | AstNode::Xor(left, right)
+        | AstNode::If(left, right)
+        | AstNode::Iff(left, right) => {
+            collect_atom_ids(left, atom_ids);
+            collect_atom_ids(right, atom_ids);
+        }
+    }
+}
+
+// Renvoie le litteral representant la valeur de verite de `ast`, en ajoutant
+// au passage les clauses qui le definissent en fonction de ses enfants.
+fn tseitin_var(ast: &AstNode, atom_ids: &mut HashMap<String, i32>, next_aux: &mut i32, clauses: &mut Vec<Vec<Lit>>) -> Lit {
+    match ast {
+        AstNode::Atom(name) => atom_id(atom_ids, name),
+        AstNode::Const(value) => {
+            let v = fresh_var(next_aux);
+            clauses.push(if *value { vec![v] } else { vec![-v] });
+            v
+        }
+        AstNode::Not(inner) => -tseitin_var(inner, atom_ids, next_aux, clauses),
+        AstNode::And(left, right) => {
+            let a = tseitin_var(left, atom_ids, next_aux, clauses);
+            let b = tseitin_var(right, atom_ids, next_aux, clauses);
+            let v = fresh_var(next_aux);
+            // v <-> (a et b)
+            clauses.push(vec![-v, a]);
+            clauses.push(vec![-v, b]);
+            clauses.push(vec![v, -a, -b]);
+            v
+        }
+        AstNode::Or(left, right) => {
+            let a = tseitin_var(left, atom_ids, next_aux, clauses);
+            let b = tseitin_var(right, atom_ids, next_aux, clauses);
+            let v = fresh_var(next_aux);
+            // v <-> (a ou b)
+            clauses.push(vec![v, -a]);
+            clauses.push(vec![v, -b]);
+            clauses.push(vec![-v, a, b]);
+            v
+        }
+        AstNode::Xor(left, right) => {
+            let positive = AstNode::And(left.clone(), Box::new(AstNode::Not(right.clone())));
+            let negative = AstNode::And(Box::new(AstNode::Not(left.clone())), right.clone());
+            tseitin_var(&AstNode::Or(Box::new(positive), Box::new(negative)), atom_ids, next_aux, clauses)
+        }
+        AstNode::If(left, right) => {
+            tseitin_var(&AstNode::Or(Box::new(AstNode::Not(left.clone())), right.clone()), atom_ids, next_aux, clauses)
+        }
+        AstNode::Iff(left, right) => {
+            let forward = AstNode::Or(Box::new(AstNode::Not(left.clone())), right.clone());
+            let backward = AstNode::Or(Box::new(AstNode::Not(right.clone())), left.clone());
+            tseitin_var(&AstNode::And(Box::new(forward), Box::new(backward)), atom_ids, next_aux, clauses)
+        }
+    }
+}
+
+fn fresh_var(next_aux: &mut i32) -> Lit {
+    let id = *next_aux;
+    *next_aux += 1;
+    id
+}
+
+// Resout un ensemble de clauses CNF par DPLL (propagation unitaire,
+// elimination des litteraux purs, puis separation sur une variable).
+// Renvoie l'affectation satisfaisante trouvee, le cas echeant.
+pub fn solve(clauses: Vec<Vec<Lit>>) -> Option<HashMap<i32, bool>> {
+    let mut assignment = HashMap::new();
+    if dpll(clauses, &mut assignment) {
+        Some(assignment)
+    } else {
+        None
+    }
+}
+
+enum Propagation {
+    Conflict,
+    Changed,
+    Done,
+}
+
+fn dpll(mut clauses: Vec<Vec<Lit>>, assignment: &mut HashMap<i32, bool>) -> bool {
+    loop {
+        match unit_propagate(&mut clauses, assignment) {
+            Propagation::Conflict => return false,
+            Propagation::Changed => continue,
+            Propagation::Done => break,
+        }
+    }
+
+    if clauses.is_empty() {
+        return true; // Toutes les clauses sont satisfaites.
+    }
+
+    if let Some(lit) = pure_literal(&clauses) {
+        assignment.insert(lit.unsigned_abs() as i32, lit > 0);
+        return dpll(simplify(&clauses, lit), assignment);
+    }
+
+    // Separe sur la premiere variable non assignee : essaie vrai puis faux.
+    let var = clauses[0][0].unsigned_abs() as i32;
+    for &value in &[true, false] {
+        let lit = if value { var } else { -var };
+        let mut branch_assignment = assignment.clone();
+        branch_assignment.insert(var, value);
+        if dpll(simplify(&clauses, lit), &mut branch_assignment) {
+            *assignment = branch_assignment;
+            return true;
+        }
+    }
+    false
+}
+
+// Propage les clauses unitaires jusqu'a point fixe ; signale un conflit des
+// qu'une clause devient vide.
+fn unit_propagate(clauses: &mut Vec<Vec<Lit>>, assignment: &mut HashMap<i32, bool>) -> Propagation {
+    if clauses.iter().any(|clause| clause.is_empty()) {
+        return Propagation::Conflict;
+    }
+    match clauses.iter().position(|clause| clause.len() == 1) {
+        Some(pos) => {
+            let unit = clauses[pos][0];
+            assignment.insert(unit.unsigned_abs() as i32, unit > 0);
+            *clauses = simplify(clauses, unit);
+            Propagation::Changed
+        }
+        None => Propagation::Done,
+    }
+}
+
+// Variable qui n'apparait que sous une seule polarite dans `clauses` : on
+// peut la fixer pour satisfaire d'un coup toutes les clauses qui la contiennent.
+fn pure_literal(clauses: &[Vec<Lit>]) -> Option<Lit> {
+    let mut polarity: HashMap<i32, Option<bool>> = HashMap::new();
+    for clause in clauses {
+        for &lit in clause {
+            let var = lit.unsigned_abs() as i32;
+            let sign = lit > 0;
+            polarity
+                .entry(var)
+                .and_modify(|existing| {
+                    if *existing != Some(sign) {
+                        *existing = None;
+                    }
+                })
+                .or_insert(Some(sign));
+        }
+    }
+    polarity.into_iter().find_map(|(var, sign)| sign.map(|positive| if positive { var } else { -var }))
+}
+
+// Retire les clauses satisfaites par `lit` et simplifie celles qui contiennent `-lit`.
+fn simplify(clauses: &[Vec<Lit>], lit: Lit) -> Vec<Vec<Lit>> {
+    clauses
+        .iter()
+        .filter(|clause| !clause.contains(&lit))
+        .map(|clause| clause.iter().copied().filter(|&l| l != -lit).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(name: &str) -> AstNode {
+        AstNode::Atom(name.to_string())
+    }
+
+    #[test]
+    fn satisfiable_conjunction() {
+        let ast = AstNode::And(Box::new(atom("a")), Box::new(atom("b")));
+        assert!(is_satisfiable(&ast));
+    }
+
+    #[test]
+    fn contradiction_is_unsatisfiable() {
+        let ast = AstNode::And(Box::new(atom("a")), Box::new(AstNode::Not(Box::new(atom("a")))));
+        assert!(!is_satisfiable(&ast));
+    }
+
+    #[test]
+    fn tautology_is_valid() {
+        let ast = AstNode::Or(Box::new(atom("a")), Box::new(AstNode::Not(Box::new(atom("a")))));
+        assert!(is_valid(&ast));
+    }
+
+    #[test]
+    fn entails_modus_ponens() {
+        let p = atom("p");
+        let q = atom("q");
+        let kb = vec![p.clone(), AstNode::If(Box::new(p), Box::new(q.clone()))];
+        assert!(entails(&kb, &q));
+    }
+
+    #[test]
+    fn find_model_returns_a_satisfying_assignment() {
+        let ast = AstNode::And(Box::new(atom("a")), Box::new(AstNode::Not(Box::new(atom("b")))));
+        let model = find_model(&ast).expect("satisfiable");
+        assert_eq!(model.get("a"), Some(&true));
+        assert_eq!(model.get("b"), Some(&false));
+    }
+
+    #[test]
+    fn find_model_returns_none_for_unsatisfiable_formula() {
+        let ast = AstNode::And(Box::new(atom("a")), Box::new(AstNode::Not(Box::new(atom("a")))));
+        assert!(find_model(&ast).is_none());
+    }
+
+    // Regression : `a` disparait de l'arbre une fois simplifie (`a ou non a`
+    // se reduit a `true`), mais `a` fait partie de la formule d'origine et
+    // doit donc rester present dans le modele renvoye, meme si sa valeur
+    // n'est pas contrainte.
+    #[test]
+    fn find_model_keeps_atoms_eliminated_by_simplification() {
+        let tautology = AstNode::Or(Box::new(atom("a")), Box::new(AstNode::Not(Box::new(atom("a")))));
+        let ast = AstNode::And(Box::new(tautology), Box::new(atom("b")));
+        let model = find_model(&ast).expect("satisfiable");
+        assert_eq!(model.get("b"), Some(&true));
+        assert!(model.contains_key("a"), "unconstrained atom `a` should still appear in the model");
+    }
+}
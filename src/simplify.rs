@@ -0,0 +1,211 @@
+use crate::parser::AstNode;
+
+// Reecrit `ast` vers une forme canonique equivalente : expanse Iff/Xor en
+// And/Or/Not/If puis applique a point fixe des regles de reecriture usuelles
+// (constant-folding, double negation, idempotence, absorption...). Utile a
+// la fois pour afficher une version lisible d'une formule et pour donner au
+// chemin CNF/SAT une entree plus petite.
+pub fn simplify(ast: &AstNode) -> AstNode {
+    let mut current = expand_connectives(ast);
+    loop {
+        let next = rewrite(&current);
+        if next == current {
+            return next;
+        }
+        current = next;
+    }
+}
+
+// Remplace Iff(a, b) par (a if b) et (b if a), et Xor(a, b) par
+// (a et non b) ou (non a et b), pour que `rewrite` n'ait que Atom/Const/Not/And/Or/If a traiter.
+fn expand_connectives(ast: &AstNode) -> AstNode {
+    match ast {
+        AstNode::Atom(_) | AstNode::Const(_) => ast.clone(),
+        AstNode::Not(inner) => AstNode::Not(Box::new(expand_connectives(inner))),
+        AstNode::And(left, right) => AstNode::And(Box::new(expand_connectives(left)), Box::new(expand_connectives(right))),
+        AstNode::Or(left, right) => AstNode::Or(Box::new(expand_connectives(left)), Box::new(expand_connectives(right))),
+        AstNode::If(left, right) => AstNode::If(Box::new(expand_connectives(left)), Box::new(expand_connectives(right))),
+        AstNode::Iff(left, right) => {
+            let left = expand_connectives(left);
+            let right = expand_connectives(right);
+            AstNode::And(
+                Box::new(AstNode::If(Box::new(left.clone()), Box::new(right.clone()))),
+                Box::new(AstNode::If(Box::new(right), Box::new(left))),
+            )
+        }
+        AstNode::Xor(left, right) => {
+            let left = expand_connectives(left);
+            let right = expand_connectives(right);
+            let positive = AstNode::And(Box::new(left.clone()), Box::new(AstNode::Not(Box::new(right.clone()))));
+            let negative = AstNode::And(Box::new(AstNode::Not(Box::new(left))), Box::new(right));
+            AstNode::Or(Box::new(positive), Box::new(negative))
+        }
+    }
+}
+
+// Applique une passe de reecriture bottom-up. Appelee a repetition par
+// `simplify` jusqu'a point fixe, car simplifier les enfants peut faire
+// apparaitre de nouvelles occasions de simplifier le parent.
+fn rewrite(ast: &AstNode) -> AstNode {
+    match ast {
+        AstNode::Atom(_) | AstNode::Const(_) => ast.clone(),
+        AstNode::Not(inner) => match rewrite(inner) {
+            AstNode::Not(double) => *double,          // non non a -> a
+            AstNode::Const(value) => AstNode::Const(!value),
+            other => AstNode::Not(Box::new(other)),
+        },
+        AstNode::And(left, right) => rewrite_and(rewrite(left), rewrite(right)),
+        AstNode::Or(left, right) => rewrite_or(rewrite(left), rewrite(right)),
+        AstNode::If(left, right) => rewrite_if(rewrite(left), rewrite(right)),
+        AstNode::Xor(_, _) | AstNode::Iff(_, _) => unreachable!("expand_connectives already removed Xor/Iff"),
+    }
+}
+
+fn rewrite_and(left: AstNode, right: AstNode) -> AstNode {
+    if matches!(left, AstNode::Const(false)) || matches!(right, AstNode::Const(false)) {
+        return AstNode::Const(false);
+    }
+    if matches!(left, AstNode::Const(true)) {
+        return right;
+    }
+    if matches!(right, AstNode::Const(true)) {
+        return left;
+    }
+    if left == right {
+        return left; // a et a -> a
+    }
+    if is_negation_of(&left, &right) || is_negation_of(&right, &left) {
+        return AstNode::Const(false); // a et non a -> false
+    }
+    if let AstNode::Or(ref disjunct_a, ref disjunct_b) = left {
+        if disjunct_a.as_ref() == &right || disjunct_b.as_ref() == &right {
+            return right; // (a ou b) et a -> a
+        }
+    }
+    if let AstNode::Or(ref disjunct_a, ref disjunct_b) = right {
+        if disjunct_a.as_ref() == &left || disjunct_b.as_ref() == &left {
+            return left; // a et (a ou b) -> a
+        }
+    }
+    AstNode::And(Box::new(left), Box::new(right))
+}
+
+fn rewrite_or(left: AstNode, right: AstNode) -> AstNode {
+    if matches!(left, AstNode::Const(true)) || matches!(right, AstNode::Const(true)) {
+        return AstNode::Const(true);
+    }
+    if matches!(left, AstNode::Const(false)) {
+        return right;
+    }
+    if matches!(right, AstNode::Const(false)) {
+        return left;
+    }
+    if left == right {
+        return left; // a ou a -> a
+    }
+    if is_negation_of(&left, &right) || is_negation_of(&right, &left) {
+        return AstNode::Const(true); // a ou non a -> true
+    }
+    if let AstNode::And(ref conjunct_a, ref conjunct_b) = left {
+        if conjunct_a.as_ref() == &right || conjunct_b.as_ref() == &right {
+            return right; // (a et b) ou a -> a
+        }
+    }
+    if let AstNode::And(ref conjunct_a, ref conjunct_b) = right {
+        if conjunct_a.as_ref() == &left || conjunct_b.as_ref() == &left {
+            return left; // a ou (a et b) -> a
+        }
+    }
+    AstNode::Or(Box::new(left), Box::new(right))
+}
+
+fn rewrite_if(left: AstNode, right: AstNode) -> AstNode {
+    if matches!(left, AstNode::Const(false)) || matches!(right, AstNode::Const(true)) {
+        return AstNode::Const(true);
+    }
+    if matches!(left, AstNode::Const(true)) {
+        return right;
+    }
+    if matches!(right, AstNode::Const(false)) {
+        return AstNode::Not(Box::new(left));
+    }
+    if left == right {
+        return AstNode::Const(true); // a if a -> true
+    }
+    AstNode::If(Box::new(left), Box::new(right))
+}
+
+fn is_negation_of(ast: &AstNode, other: &AstNode) -> bool {
+    matches!(ast, AstNode::Not(inner) if inner.as_ref() == other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(name: &str) -> AstNode {
+        AstNode::Atom(name.to_string())
+    }
+
+    #[test]
+    fn double_negation_cancels() {
+        let ast = AstNode::Not(Box::new(AstNode::Not(Box::new(atom("a")))));
+        assert_eq!(simplify(&ast), atom("a"));
+    }
+
+    #[test]
+    fn and_idempotence() {
+        let ast = AstNode::And(Box::new(atom("a")), Box::new(atom("a")));
+        assert_eq!(simplify(&ast), atom("a"));
+    }
+
+    #[test]
+    fn or_idempotence() {
+        let ast = AstNode::Or(Box::new(atom("a")), Box::new(atom("a")));
+        assert_eq!(simplify(&ast), atom("a"));
+    }
+
+    #[test]
+    fn and_with_negation_folds_to_false() {
+        let ast = AstNode::And(Box::new(atom("a")), Box::new(AstNode::Not(Box::new(atom("a")))));
+        assert_eq!(simplify(&ast), AstNode::Const(false));
+    }
+
+    #[test]
+    fn or_with_negation_folds_to_true() {
+        let ast = AstNode::Or(Box::new(atom("a")), Box::new(AstNode::Not(Box::new(atom("a")))));
+        assert_eq!(simplify(&ast), AstNode::Const(true));
+    }
+
+    #[test]
+    fn absorption_or_over_and() {
+        // (a et b) ou a -> a
+        let ast = AstNode::Or(
+            Box::new(AstNode::And(Box::new(atom("a")), Box::new(atom("b")))),
+            Box::new(atom("a")),
+        );
+        assert_eq!(simplify(&ast), atom("a"));
+    }
+
+    #[test]
+    fn absorption_and_over_or() {
+        // (a ou b) et a -> a
+        let ast = AstNode::And(
+            Box::new(AstNode::Or(Box::new(atom("a")), Box::new(atom("b")))),
+            Box::new(atom("a")),
+        );
+        assert_eq!(simplify(&ast), atom("a"));
+    }
+
+    #[test]
+    fn iff_expands_and_folds_when_equal() {
+        let ast = AstNode::Iff(Box::new(atom("a")), Box::new(atom("a")));
+        assert_eq!(simplify(&ast), AstNode::Const(true));
+    }
+
+    #[test]
+    fn xor_expands_and_folds_when_equal() {
+        let ast = AstNode::Xor(Box::new(atom("a")), Box::new(atom("a")));
+        assert_eq!(simplify(&ast), AstNode::Const(false));
+    }
+}